@@ -4,14 +4,25 @@ use base64::Engine;
 /// Generate an AI description for a media file.
 /// For images: encode as base64 data URI using ZeroClaw's [IMAGE:] marker system.
 /// For audio/video: return basic metadata description.
+///
+/// `thumbnail`, when present, is a pre-downscaled JPEG (see `preview`) and is
+/// emitted in the `[IMAGE:]` marker instead of the full original — the
+/// original can be multiple megabytes, the thumbnail is capped at 512px.
+/// Pass `None` to always describe the full original (e.g. no `preview`
+/// backend compiled in).
 pub fn describe_media(
     data: &[u8],
     mime_type: &str,
     filename: &str,
+    thumbnail: Option<&[u8]>,
 ) -> Result<Option<String>> {
     if mime_type.starts_with("image/") {
-        let b64 = base64::engine::general_purpose::STANDARD.encode(data);
-        let data_uri = format!("data:{};base64,{}", mime_type, b64);
+        let (bytes, mime) = match thumbnail {
+            Some(thumb) => (thumb, "image/jpeg"),
+            None => (data, mime_type),
+        };
+        let b64 = base64::engine::general_purpose::STANDARD.encode(bytes);
+        let data_uri = format!("data:{};base64,{}", mime, b64);
         Ok(Some(format!(
             "[Uploaded image: {}]\n[IMAGE:{}]",
             filename, data_uri
@@ -23,12 +34,29 @@ pub fn describe_media(
             data.len()
         )))
     } else if mime_type.starts_with("video/") {
-        Ok(Some(format!(
+        let base = format!(
             "[Uploaded video file: {}, size: {} bytes]",
             filename,
             data.len()
-        )))
+        );
+        match thumbnail {
+            Some(thumb) => {
+                let b64 = base64::engine::general_purpose::STANDARD.encode(thumb);
+                let data_uri = format!("data:image/jpeg;base64,{}", b64);
+                Ok(Some(format!("{}\n[IMAGE:{}]", base, data_uri)))
+            }
+            None => Ok(Some(base)),
+        }
     } else {
         Ok(None)
     }
 }
+
+/// Strip the `[IMAGE:data:...]` marker `describe_media` appends for images
+/// and video, keeping only the text before it (e.g. `[Uploaded image:
+/// foo.png]`). The marker is a base64 data URI meant for chat rendering —
+/// meaningless and oversized input for a text embedder, so callers that
+/// embed `ai_description` for semantic search should strip it first.
+pub fn strip_image_marker(description: &str) -> &str {
+    description.split("\n[IMAGE:").next().unwrap_or(description)
+}
@@ -0,0 +1,13 @@
+use anyhow::Result;
+
+/// Produces dense embeddings for text so FTMS can rank by semantic
+/// similarity instead of just keyword overlap.
+///
+/// FTMS has no opinion on which model backs this — implementations wire in
+/// whatever embedding model ZeroClaw is already configured to use, so the
+/// index itself stays free of a hard dependency on any one of them.
+pub trait Embedder: Send + Sync {
+    /// Embed `text` into a dense vector. Implementations should L2-normalize
+    /// the result so callers can rank candidates by plain dot product.
+    fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
@@ -3,12 +3,196 @@ use anyhow::Result;
 /// Maximum text to extract (100KB) to avoid bloating the index.
 const MAX_TEXT_LEN: usize = 102_400;
 
+/// Embedded tags and container metadata pulled from an audio or video file.
+/// Folded into `FileRecord.extracted_text`/`tags` so a search for an artist
+/// or album surfaces the file even though `extract_text` itself returns
+/// `None` for these MIME types.
+#[derive(Debug, Clone, Default)]
+pub struct MediaMetadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<String>,
+    pub track: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub codec: Option<String>,
+}
+
+impl MediaMetadata {
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.artist.is_none()
+            && self.album.is_none()
+            && self.year.is_none()
+            && self.track.is_none()
+            && self.duration_secs.is_none()
+            && self.width.is_none()
+            && self.height.is_none()
+            && self.codec.is_none()
+    }
+
+    /// Render as a human-readable blob for `extracted_text`/FTS indexing.
+    pub fn to_text(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut lines = Vec::new();
+        if let Some(t) = &self.title {
+            lines.push(format!("Title: {}", t));
+        }
+        if let Some(a) = &self.artist {
+            lines.push(format!("Artist: {}", a));
+        }
+        if let Some(a) = &self.album {
+            lines.push(format!("Album: {}", a));
+        }
+        if let Some(y) = &self.year {
+            lines.push(format!("Year: {}", y));
+        }
+        if let Some(t) = &self.track {
+            lines.push(format!("Track: {}", t));
+        }
+        if let Some(c) = &self.codec {
+            lines.push(format!("Codec: {}", c));
+        }
+        if let (Some(w), Some(h)) = (self.width, self.height) {
+            lines.push(format!("Resolution: {}x{}", w, h));
+        }
+        if let Some(d) = self.duration_secs {
+            lines.push(format!("Duration: {:.1}s", d));
+        }
+        Some(lines.join("\n"))
+    }
+
+    /// Render as a comma-separated tag list (artist/album/title) so a plain
+    /// tags search surfaces the file even without matching `extracted_text`.
+    pub fn to_tags(&self) -> Option<String> {
+        let tags: Vec<&str> = [&self.artist, &self.album, &self.title]
+            .into_iter()
+            .flatten()
+            .map(|s| s.as_str())
+            .collect();
+        if tags.is_empty() {
+            None
+        } else {
+            Some(tags.join(", "))
+        }
+    }
+}
+
+/// Extract embedded tags/container metadata for `audio/*` and `video/*`
+/// uploads. Returns `None` for anything else, or if metadata extraction
+/// isn't compiled in.
+pub fn extract_media_metadata(data: &[u8], mime_type: &str) -> Result<Option<MediaMetadata>> {
+    if mime_type.starts_with("audio/") {
+        extract_audio_metadata(data)
+    } else if mime_type.starts_with("video/") {
+        extract_video_metadata(data)
+    } else {
+        Ok(None)
+    }
+}
+
+fn extract_audio_metadata(data: &[u8]) -> Result<Option<MediaMetadata>> {
+    #[cfg(feature = "rag-media-tags")]
+    {
+        use lofty::file::{AudioFile, TaggedFileExt};
+        use lofty::probe::Probe;
+        use lofty::tag::Accessor;
+        use std::io::Cursor;
+
+        let tagged_file = Probe::new(Cursor::new(data)).guess_file_type()?.read()?;
+        let properties = tagged_file.properties();
+        let tag = tagged_file.primary_tag().or_else(|| tagged_file.first_tag());
+
+        let metadata = MediaMetadata {
+            title: tag.and_then(|t| t.title().map(|s| s.to_string())),
+            artist: tag.and_then(|t| t.artist().map(|s| s.to_string())),
+            album: tag.and_then(|t| t.album().map(|s| s.to_string())),
+            year: tag.and_then(|t| t.year().map(|y| y.to_string())),
+            track: tag.and_then(|t| t.track().map(|n| n.to_string())),
+            duration_secs: Some(properties.duration().as_secs_f64()),
+            width: None,
+            height: None,
+            codec: None,
+        };
+        Ok(if metadata.is_empty() { None } else { Some(metadata) })
+    }
+    #[cfg(not(feature = "rag-media-tags"))]
+    {
+        let _ = data;
+        Ok(None)
+    }
+}
+
+/// Probe container/stream metadata via the system `ffprobe` binary.
+fn extract_video_metadata(data: &[u8]) -> Result<Option<MediaMetadata>> {
+    #[cfg(feature = "rag-media-tags")]
+    {
+        use std::io::Write;
+        use std::process::Command;
+
+        let mut src = tempfile::Builder::new().suffix(".bin").tempfile()?;
+        src.write_all(data)?;
+
+        let output = Command::new("ffprobe")
+            .args([
+                "-v", "error", "-show_format", "-show_streams", "-of", "json",
+            ])
+            .arg(src.path())
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let probe: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let format = &probe["format"];
+        let video_stream = probe["streams"]
+            .as_array()
+            .and_then(|streams| streams.iter().find(|s| s["codec_type"] == "video"));
+
+        let metadata = MediaMetadata {
+            title: format["tags"]["title"].as_str().map(|s| s.to_string()),
+            artist: None,
+            album: None,
+            year: None,
+            track: None,
+            duration_secs: format["duration"]
+                .as_str()
+                .and_then(|d| d.parse::<f64>().ok()),
+            width: video_stream.and_then(|s| s["width"].as_u64()).map(|w| w as u32),
+            height: video_stream.and_then(|s| s["height"].as_u64()).map(|h| h as u32),
+            codec: video_stream
+                .and_then(|s| s["codec_name"].as_str())
+                .map(|s| s.to_string()),
+        };
+        Ok(if metadata.is_empty() { None } else { Some(metadata) })
+    }
+    #[cfg(not(feature = "rag-media-tags"))]
+    {
+        let _ = data;
+        Ok(None)
+    }
+}
+
 /// Extract text content from a file based on its MIME type.
 /// Returns None for binary/media files that need AI description instead.
 pub fn extract_text(data: &[u8], mime_type: &str, _filename: &str) -> Result<Option<String>> {
     match mime_type {
+        // HTML — strip scripts/styles/nav chrome and keep the readable
+        // article text, same cleaning `FtmsService::upload_url` applies to
+        // fetched pages. Falls back to a raw decode if `rag-web` isn't
+        // compiled in or the cleaning finds no readable text.
+        "text/html" => {
+            let raw = String::from_utf8_lossy(data).to_string();
+            let text = super::webfetch::clean_html(&raw).unwrap_or(raw);
+            Ok(truncate_text(text))
+        }
+
         // Plain text types — direct UTF-8 decode
-        "text/plain" | "text/markdown" | "text/csv" | "text/html" | "text/xml"
+        "text/plain" | "text/markdown" | "text/csv" | "text/xml"
         | "application/json" | "application/xml" => {
             let text = String::from_utf8_lossy(data).to_string();
             Ok(truncate_text(text))
@@ -88,3 +272,48 @@ pub fn guess_mime_type(filename: &str) -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_metadata_to_text_renders_populated_fields_only() {
+        let metadata = MediaMetadata {
+            title: Some("Song".to_string()),
+            artist: Some("Artist".to_string()),
+            duration_secs: Some(125.4),
+            ..Default::default()
+        };
+        let text = metadata.to_text().unwrap();
+        assert!(text.contains("Title: Song"));
+        assert!(text.contains("Artist: Artist"));
+        assert!(text.contains("Duration: 125.4s"));
+        assert!(!text.contains("Album"));
+    }
+
+    #[test]
+    fn media_metadata_to_text_is_none_when_empty() {
+        assert!(MediaMetadata::default().to_text().is_none());
+    }
+
+    #[test]
+    fn media_metadata_to_tags_joins_artist_album_title() {
+        let metadata = MediaMetadata {
+            title: Some("Song".to_string()),
+            artist: Some("Artist".to_string()),
+            album: Some("Album".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(metadata.to_tags().unwrap(), "Artist, Album, Song");
+    }
+
+    #[test]
+    fn media_metadata_to_tags_is_none_without_artist_album_title() {
+        let metadata = MediaMetadata {
+            duration_secs: Some(10.0),
+            ..Default::default()
+        };
+        assert!(metadata.to_tags().is_none());
+    }
+}
@@ -1,10 +1,17 @@
-use super::schema::{FileRecord, FileSearchResult, FileListResponse};
+use super::schema::{FileRecord, FileSearchResult, FileListResponse, FileStatus};
 use anyhow::{Context, Result};
 use parking_lot::Mutex;
 use rusqlite::{params, Connection};
+use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
 
+/// Constant from the reciprocal-rank-fusion formula used by `hybrid_search`:
+/// score = Σ 1/(k + rank_i). Larger k flattens the influence of rank 1 vs.
+/// rank 50; 60 is the commonly cited default for this kind of fusion.
+const RRF_K: f64 = 60.0;
+
 /// SQLite-backed file index with FTS5 full-text search.
 pub struct FileIndex {
     conn: Arc<Mutex<Connection>>,
@@ -37,17 +44,27 @@ impl FileIndex {
                 mime_type       TEXT NOT NULL,
                 file_path       TEXT NOT NULL,
                 file_size       INTEGER NOT NULL,
+                content_hash    TEXT NOT NULL,
                 extracted_text  TEXT,
                 ai_description  TEXT,
+                thumbnail_path  TEXT,
+                width           INTEGER,
+                height          INTEGER,
+                duration_secs   REAL,
+                status          TEXT NOT NULL DEFAULT 'pending',
+                error           TEXT,
                 session_id      TEXT,
                 channel         TEXT,
                 uploaded_at     TEXT NOT NULL,
-                tags            TEXT
+                tags            TEXT,
+                source_url      TEXT
             );
 
             CREATE INDEX IF NOT EXISTS idx_ftms_session ON ftms_files(session_id);
             CREATE INDEX IF NOT EXISTS idx_ftms_uploaded ON ftms_files(uploaded_at);
             CREATE INDEX IF NOT EXISTS idx_ftms_mime ON ftms_files(mime_type);
+            CREATE INDEX IF NOT EXISTS idx_ftms_hash ON ftms_files(content_hash);
+            CREATE INDEX IF NOT EXISTS idx_ftms_status ON ftms_files(status);
 
             CREATE VIRTUAL TABLE IF NOT EXISTS ftms_fts USING fts5(
                 filename, extracted_text, ai_description, tags,
@@ -69,8 +86,61 @@ impl FileIndex {
                 VALUES ('delete', old.rowid, old.filename, old.extracted_text, old.ai_description, old.tags);
                 INSERT INTO ftms_fts(rowid, filename, extracted_text, ai_description, tags)
                 VALUES (new.rowid, new.filename, new.extracted_text, new.ai_description, new.tags);
-            END;",
+            END;
+
+            CREATE TABLE IF NOT EXISTS file_vectors (
+                id     TEXT PRIMARY KEY REFERENCES ftms_files(id),
+                vector BLOB NOT NULL
+            );",
         ).context("Failed to init FTMS schema")?;
+
+        Self::migrate_files_table(conn)?;
+        Ok(())
+    }
+
+    /// `CREATE TABLE IF NOT EXISTS` above only lays down the full schema for
+    /// a brand-new database. Against an `ftms.db` written by an older
+    /// version of FTMS, `ftms_files` is left exactly as it was and any
+    /// column added since is simply missing, so `insert`/`row_to_record`
+    /// fail. Add each one with `ALTER TABLE ... ADD COLUMN` if it isn't
+    /// there yet.
+    fn migrate_files_table(conn: &Connection) -> Result<()> {
+        let mut existing = std::collections::HashSet::new();
+        let mut stmt = conn.prepare("PRAGMA table_info(ftms_files)")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            existing.insert(row.get::<_, String>(1)?);
+        }
+        drop(rows);
+        drop(stmt);
+
+        let columns: &[(&str, &str)] = &[
+            ("content_hash", "TEXT NOT NULL DEFAULT ''"),
+            ("thumbnail_path", "TEXT"),
+            ("width", "INTEGER"),
+            ("height", "INTEGER"),
+            ("duration_secs", "REAL"),
+            ("status", "TEXT NOT NULL DEFAULT 'pending'"),
+            ("error", "TEXT"),
+            ("source_url", "TEXT"),
+        ];
+        for (name, def) in columns {
+            if !existing.contains(*name) {
+                conn.execute(
+                    &format!("ALTER TABLE ftms_files ADD COLUMN {} {}", name, def),
+                    [],
+                ).with_context(|| format!("Failed to add ftms_files.{} column", name))?;
+            }
+        }
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ftms_hash ON ftms_files(content_hash)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ftms_status ON ftms_files(status)",
+            [],
+        )?;
         Ok(())
     }
 
@@ -79,12 +149,15 @@ impl FileIndex {
         let conn = self.conn.lock();
         conn.execute(
             "INSERT INTO ftms_files (id, filename, mime_type, file_path, file_size,
-             extracted_text, ai_description, session_id, channel, uploaded_at, tags)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+             content_hash, extracted_text, ai_description, thumbnail_path, width, height, duration_secs,
+             status, error, session_id, channel, uploaded_at, tags, source_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
             params![
                 record.id, record.filename, record.mime_type, record.file_path,
-                record.file_size, record.extracted_text, record.ai_description,
-                record.session_id, record.channel, record.uploaded_at, record.tags,
+                record.file_size, record.content_hash, record.extracted_text, record.ai_description,
+                record.thumbnail_path, record.width, record.height, record.duration_secs,
+                record.status.as_str(), record.error,
+                record.session_id, record.channel, record.uploaded_at, record.tags, record.source_url,
             ],
         ).context("Failed to insert file record")?;
         Ok(())
@@ -100,29 +173,69 @@ impl FileIndex {
         Ok(())
     }
 
+    /// Update the thumbnail path (for async processing).
+    pub fn update_thumbnail(&self, id: &str, thumbnail_path: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE ftms_files SET thumbnail_path = ?1 WHERE id = ?2",
+            params![thumbnail_path, id],
+        ).context("Failed to update file thumbnail")?;
+        Ok(())
+    }
+
+    /// Update width/height/duration (for async processing).
+    pub fn update_dimensions(
+        &self,
+        id: &str,
+        width: Option<u32>,
+        height: Option<u32>,
+        duration_secs: Option<f64>,
+    ) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE ftms_files SET width = ?1, height = ?2, duration_secs = ?3 WHERE id = ?4",
+            params![width, height, duration_secs, id],
+        ).context("Failed to update file dimensions")?;
+        Ok(())
+    }
+
+    /// Append to the comma-separated `tags` column rather than overwriting
+    /// it, so tags derived during background processing (e.g. an artist
+    /// name pulled from ID3 tags) don't clobber tags set at upload time.
+    pub fn append_tags(&self, id: &str, extra: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE ftms_files SET tags = CASE
+                WHEN tags IS NULL OR tags = '' THEN ?1
+                ELSE tags || ', ' || ?1
+             END WHERE id = ?2",
+            params![extra, id],
+        ).context("Failed to append file tags")?;
+        Ok(())
+    }
+
+    /// Move a file to a new pipeline status. `error` is recorded alongside
+    /// `FileStatus::Failed` and cleared on any other transition.
+    pub fn update_status(&self, id: &str, status: FileStatus, error: Option<&str>) -> Result<()> {
+        let error = if status == FileStatus::Failed { error } else { None };
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE ftms_files SET status = ?1, error = ?2 WHERE id = ?3",
+            params![status.as_str(), error, id],
+        ).context("Failed to update file status")?;
+        Ok(())
+    }
+
     /// Get a file record by ID.
     pub fn get(&self, id: &str) -> Result<Option<FileRecord>> {
         let conn = self.conn.lock();
         let mut stmt = conn.prepare(
-            "SELECT id, filename, mime_type, file_path, file_size, extracted_text,
-             ai_description, session_id, channel, uploaded_at, tags
+            "SELECT id, filename, mime_type, file_path, file_size, content_hash, extracted_text,
+             ai_description, thumbnail_path, width, height, duration_secs, status, error,
+             session_id, channel, uploaded_at, tags, source_url
              FROM ftms_files WHERE id = ?1",
         )?;
-        let result = stmt.query_row(params![id], |row| {
-            Ok(FileRecord {
-                id: row.get(0)?,
-                filename: row.get(1)?,
-                mime_type: row.get(2)?,
-                file_path: row.get(3)?,
-                file_size: row.get::<_, i64>(4)? as u64,
-                extracted_text: row.get(5)?,
-                ai_description: row.get(6)?,
-                session_id: row.get(7)?,
-                channel: row.get(8)?,
-                uploaded_at: row.get(9)?,
-                tags: row.get(10)?,
-            })
-        });
+        let result = stmt.query_row(params![id], Self::row_to_record);
         match result {
             Ok(r) => Ok(Some(r)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
@@ -130,19 +243,71 @@ impl FileIndex {
         }
     }
 
-    /// List files with pagination, optionally filtered by session_id or mime_type.
+    /// Get the most recent file record with a given content hash, if any.
+    pub fn get_by_hash(&self, content_hash: &str) -> Result<Option<FileRecord>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, filename, mime_type, file_path, file_size, content_hash, extracted_text,
+             ai_description, thumbnail_path, width, height, duration_secs, status, error,
+             session_id, channel, uploaded_at, tags, source_url
+             FROM ftms_files WHERE content_hash = ?1 ORDER BY uploaded_at DESC LIMIT 1",
+        )?;
+        let result = stmt.query_row(params![content_hash], Self::row_to_record);
+        match result {
+            Ok(r) => Ok(Some(r)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Count how many records currently reference a content hash.
+    pub fn count_by_hash(&self, content_hash: &str) -> Result<i64> {
+        let conn = self.conn.lock();
+        conn.query_row(
+            "SELECT COUNT(*) FROM ftms_files WHERE content_hash = ?1",
+            params![content_hash],
+            |row| row.get(0),
+        ).context("Failed to count file records by hash")
+    }
+
+    /// Count how many records currently reference a thumbnail blob path.
+    /// Thumbnails are their own content-addressed blob (keyed by the
+    /// thumbnail bytes' hash, not the original file's), so they need their
+    /// own reference count — see `FtmsService::delete`.
+    pub fn count_by_thumbnail_path(&self, thumbnail_path: &str) -> Result<i64> {
+        let conn = self.conn.lock();
+        conn.query_row(
+            "SELECT COUNT(*) FROM ftms_files WHERE thumbnail_path = ?1",
+            params![thumbnail_path],
+            |row| row.get(0),
+        ).context("Failed to count file records by thumbnail path")
+    }
+
+    /// Delete a file record by ID. Does not touch the underlying blob —
+    /// see `FtmsService::delete` for reference-counted blob cleanup.
+    pub fn delete(&self, id: &str) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute("DELETE FROM ftms_files WHERE id = ?1", params![id])
+            .context("Failed to delete file record")?;
+        Ok(())
+    }
+
+    /// List files with pagination, optionally filtered by `session_id`,
+    /// `mime_prefix`, and/or pipeline `status`. Pass `None` for any filter
+    /// to leave it unrestricted.
     pub fn list(
         &self,
         offset: usize,
         limit: usize,
         session_id: Option<&str>,
         mime_prefix: Option<&str>,
+        status: Option<FileStatus>,
     ) -> Result<FileListResponse> {
         let conn = self.conn.lock();
 
         // Build dynamic query
         let (where_sql, count_params, query_params) = Self::build_filter(
-            session_id, mime_prefix, offset, limit,
+            session_id, mime_prefix, status, offset, limit,
         );
 
         let count: usize = conn.query_row(
@@ -152,8 +317,9 @@ impl FileIndex {
         )?;
 
         let sql = format!(
-            "SELECT id, filename, mime_type, file_path, file_size, extracted_text,
-             ai_description, session_id, channel, uploaded_at, tags
+            "SELECT id, filename, mime_type, file_path, file_size, content_hash, extracted_text,
+             ai_description, thumbnail_path, width, height, duration_secs, status, error,
+             session_id, channel, uploaded_at, tags, source_url
              FROM ftms_files {} ORDER BY uploaded_at DESC LIMIT ? OFFSET ?",
             where_sql,
         );
@@ -168,44 +334,186 @@ impl FileIndex {
         Ok(FileListResponse { files, total: count, offset, limit })
     }
 
-    /// Full-text search using FTS5.
-    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<FileSearchResult>> {
+    /// List files in a given pipeline status, most recently uploaded first —
+    /// e.g. `list_by_status(FileStatus::Failed, ..)` to find stuck items to
+    /// retry with `FtmsService::reprocess`.
+    pub fn list_by_status(&self, status: FileStatus, offset: usize, limit: usize) -> Result<FileListResponse> {
+        self.list(offset, limit, None, None, Some(status))
+    }
+
+    /// Full-text search using FTS5. When `only_indexed` is set, files that
+    /// haven't finished background processing are excluded — the FTS index
+    /// only ever reflects what's been written so far, so a `pending` file
+    /// with no `extracted_text` yet would otherwise show up as an empty,
+    /// low-value match. Pass `false` to search regardless of pipeline status.
+    pub fn search(&self, query: &str, limit: usize, only_indexed: bool) -> Result<Vec<FileSearchResult>> {
         let conn = self.conn.lock();
-        let mut stmt = conn.prepare(
-            "SELECT f.id, f.filename, f.mime_type, f.file_path, f.file_size,
-             f.extracted_text, f.ai_description, f.session_id, f.channel,
-             f.uploaded_at, f.tags, ftms_fts.rank
+        let status_clause = if only_indexed { "AND f.status = 'indexed'" } else { "" };
+        let sql = format!(
+            "SELECT f.id, f.filename, f.mime_type, f.file_path, f.file_size, f.content_hash,
+             f.extracted_text, f.ai_description, f.thumbnail_path, f.width, f.height, f.duration_secs,
+             f.status, f.error, f.session_id, f.channel, f.uploaded_at, f.tags, f.source_url, ftms_fts.rank
              FROM ftms_fts
              JOIN ftms_files f ON f.rowid = ftms_fts.rowid
-             WHERE ftms_fts MATCH ?1
+             WHERE ftms_fts MATCH ?1 {}
              ORDER BY rank
              LIMIT ?2",
-        )?;
+            status_clause,
+        );
+        let mut stmt = conn.prepare(&sql)?;
         let rows = stmt.query_map(params![query, limit as i64], |row| {
             Ok(FileSearchResult {
-                file: FileRecord {
-                    id: row.get(0)?,
-                    filename: row.get(1)?,
-                    mime_type: row.get(2)?,
-                    file_path: row.get(3)?,
-                    file_size: row.get::<_, i64>(4)? as u64,
-                    extracted_text: row.get(5)?,
-                    ai_description: row.get(6)?,
-                    session_id: row.get(7)?,
-                    channel: row.get(8)?,
-                    uploaded_at: row.get(9)?,
-                    tags: row.get(10)?,
-                },
-                rank: row.get(11)?,
+                file: Self::row_to_record(row)?,
+                rank: row.get(19)?,
             })
         })?;
         Ok(rows.filter_map(|r| r.ok()).collect())
     }
 
+    /// Store (or replace) the embedding for a file. `vector` should already
+    /// be L2-normalized so similarity reduces to a plain dot product.
+    pub fn upsert_vector(&self, id: &str, vector: &[f32]) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO file_vectors (id, vector) VALUES (?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET vector = excluded.vector",
+            params![id, Self::encode_vector(vector)],
+        ).context("Failed to upsert file vector")?;
+        Ok(())
+    }
+
+    /// Fetch the stored embedding for a file, if it's been embedded.
+    /// Used to copy a vector onto a duplicate record's id (see
+    /// `FtmsService::upload`'s content-hash dedup) without re-embedding.
+    pub fn get_vector(&self, id: &str) -> Result<Option<Vec<f32>>> {
+        let conn = self.conn.lock();
+        let result = conn.query_row(
+            "SELECT vector FROM file_vectors WHERE id = ?1",
+            params![id],
+            |row| row.get::<_, Vec<u8>>(0),
+        );
+        match result {
+            Ok(blob) => Ok(Some(Self::decode_vector(&blob))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Semantic search: rank every embedded file by dot-product similarity
+    /// to `query_embedding`.
+    ///
+    /// This is a flat (single-partition) index — an exhaustive scan over
+    /// `file_vectors` — which keeps the implementation simple and is exact
+    /// rather than approximate. It's the right default until corpus size
+    /// actually demands a real IVF/HNSW partitioning: per-file uploads are
+    /// sized in the thousands, not millions, so a flat scan costs single-
+    /// digit milliseconds and an ANN structure would only add complexity
+    /// (graph maintenance, recall tuning) with no latency win. Revisit if
+    /// `file_vectors` grows past that range.
+    pub fn semantic_search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<FileSearchResult>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.filename, f.mime_type, f.file_path, f.file_size, f.content_hash,
+             f.extracted_text, f.ai_description, f.thumbnail_path, f.width, f.height, f.duration_secs,
+             f.status, f.error, f.session_id, f.channel, f.uploaded_at, f.tags, f.source_url,
+             v.vector
+             FROM file_vectors v JOIN ftms_files f ON f.id = v.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let file = Self::row_to_record(row)?;
+            let blob: Vec<u8> = row.get(19)?;
+            Ok((file, blob))
+        })?;
+
+        let mut scored: Vec<(FileRecord, f64)> = rows
+            .filter_map(|r| r.ok())
+            .filter_map(|(file, blob)| {
+                let vector = Self::decode_vector(&blob);
+                // `dot` zips the two slices and would silently truncate to
+                // the shorter one on a dimension mismatch (e.g. the
+                // embedder was swapped for one with a different output
+                // size), producing a similarity score that looks valid but
+                // isn't comparable to the rest. Skip rather than corrupt
+                // the ranking.
+                if vector.len() != query_embedding.len() {
+                    return None;
+                }
+                let similarity = Self::dot(&vector, query_embedding);
+                Some((file, similarity as f64))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+
+        Ok(scored.into_iter().map(|(file, rank)| FileSearchResult { file, rank }).collect())
+    }
+
+    /// Merge keyword (`search`) and semantic (`semantic_search`) results via
+    /// reciprocal-rank fusion: score = Σ 1/(k + rank_i), so a file ranked
+    /// highly by either signal floats to the top even without agreement
+    /// from the other.
+    pub fn hybrid_search(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        limit: usize,
+        only_indexed: bool,
+    ) -> Result<Vec<FileSearchResult>> {
+        let pool = (limit * 4).max(40);
+        let fts_results = self.search(query, pool, only_indexed)?;
+        let semantic_results = self.semantic_search(query_embedding, pool)?;
+
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        let mut files: HashMap<String, FileRecord> = HashMap::new();
+
+        // Score each list separately — rank is relative to its own list.
+        for (rank, result) in fts_results.into_iter().enumerate() {
+            if only_indexed && result.file.status != FileStatus::Indexed {
+                continue;
+            }
+            *scores.entry(result.file.id.clone()).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f64);
+            files.entry(result.file.id.clone()).or_insert(result.file);
+        }
+        for (rank, result) in semantic_results.into_iter().enumerate() {
+            if only_indexed && result.file.status != FileStatus::Indexed {
+                continue;
+            }
+            *scores.entry(result.file.id.clone()).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f64);
+            files.entry(result.file.id.clone()).or_insert(result.file);
+        }
+
+        let mut merged: Vec<FileSearchResult> = scores
+            .into_iter()
+            .filter_map(|(id, rank)| files.remove(&id).map(|file| FileSearchResult { file, rank }))
+            .collect();
+        merged.sort_by(|a, b| b.rank.partial_cmp(&a.rank).unwrap_or(std::cmp::Ordering::Equal));
+        merged.truncate(limit);
+        Ok(merged)
+    }
+
+    fn encode_vector(vector: &[f32]) -> Vec<u8> {
+        vector.iter().flat_map(|f| f.to_le_bytes()).collect()
+    }
+
+    fn decode_vector(blob: &[u8]) -> Vec<f32> {
+        blob.chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+
+    /// Callers must ensure `a.len() == b.len()` — `zip` silently truncates
+    /// to the shorter vector otherwise. `semantic_search` enforces this
+    /// before calling in.
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+    }
+
     // Helper: build WHERE clause and params for list()
     fn build_filter(
         session_id: Option<&str>,
         mime_prefix: Option<&str>,
+        status: Option<FileStatus>,
         offset: usize,
         limit: usize,
     ) -> (String, Vec<String>, Vec<String>) {
@@ -224,6 +532,11 @@ impl FileIndex {
             count_params.push(like.clone());
             query_params.push(like);
         }
+        if let Some(status) = status {
+            clauses.push("status = ?".to_string());
+            count_params.push(status.as_str().to_string());
+            query_params.push(status.as_str().to_string());
+        }
 
         let where_sql = if clauses.is_empty() {
             String::new()
@@ -238,18 +551,135 @@ impl FileIndex {
     }
 
     fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<FileRecord> {
+        let status_text: String = row.get(12)?;
+        let status = FileStatus::from_str(&status_text).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(12, rusqlite::types::Type::Text, e.into())
+        })?;
         Ok(FileRecord {
             id: row.get(0)?,
             filename: row.get(1)?,
             mime_type: row.get(2)?,
             file_path: row.get(3)?,
             file_size: row.get::<_, i64>(4)? as u64,
-            extracted_text: row.get(5)?,
-            ai_description: row.get(6)?,
-            session_id: row.get(7)?,
-            channel: row.get(8)?,
-            uploaded_at: row.get(9)?,
-            tags: row.get(10)?,
+            content_hash: row.get(5)?,
+            extracted_text: row.get(6)?,
+            ai_description: row.get(7)?,
+            thumbnail_path: row.get(8)?,
+            width: row.get::<_, Option<i64>>(9)?.map(|w| w as u32),
+            height: row.get::<_, Option<i64>>(10)?.map(|h| h as u32),
+            duration_secs: row.get(11)?,
+            status,
+            error: row.get(13)?,
+            session_id: row.get(14)?,
+            channel: row.get(15)?,
+            uploaded_at: row.get(16)?,
+            tags: row.get(17)?,
+            source_url: row.get(18)?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_index() -> (tempfile::TempDir, FileIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        let index = FileIndex::new(dir.path()).unwrap();
+        (dir, index)
+    }
+
+    fn test_record(id: &str, filename: &str, extracted_text: &str) -> FileRecord {
+        FileRecord {
+            id: id.to_string(),
+            filename: filename.to_string(),
+            mime_type: "text/plain".to_string(),
+            file_path: format!("{}.txt", id),
+            file_size: extracted_text.len() as u64,
+            content_hash: format!("hash-{}", id),
+            extracted_text: Some(extracted_text.to_string()),
+            ai_description: None,
+            thumbnail_path: None,
+            width: None,
+            height: None,
+            duration_secs: None,
+            status: FileStatus::Indexed,
+            error: None,
+            session_id: None,
+            channel: None,
+            uploaded_at: "2026-01-01T00:00:00+00:00".to_string(),
+            tags: None,
+            source_url: None,
+        }
+    }
+
+    #[test]
+    fn hybrid_search_ranks_a_file_matching_both_signals_above_one_matching_only_one() {
+        let (_dir, index) = test_index();
+
+        // "invoice" appears in both `keyword_only` and `both_signals`, so
+        // both show up in the FTS list. Only `both_signals`'s vector is
+        // aligned with the query embedding (`keyword_only`'s is orthogonal,
+        // so it scores 0.0 and comes last in the semantic list) — so
+        // `both_signals` is the only file near the top of *both* lists, and
+        // RRF fusion should rank it first even though `semantic_only` ties
+        // it on pure vector similarity.
+        index.insert(&test_record("keyword_only", "a.txt", "invoice from last quarter")).unwrap();
+        index.insert(&test_record("both_signals", "b.txt", "invoice and receipt bundle")).unwrap();
+        index.insert(&test_record("semantic_only", "c.txt", "unrelated notes")).unwrap();
+
+        index.upsert_vector("keyword_only", &[0.0, 1.0]).unwrap();
+        index.upsert_vector("both_signals", &[1.0, 0.0]).unwrap();
+        index.upsert_vector("semantic_only", &[1.0, 0.0]).unwrap();
+
+        let results = index.hybrid_search("invoice", &[1.0, 0.0], 10, true).unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.file.id.as_str()).collect();
+
+        assert_eq!(ids[0], "both_signals");
+        assert!(ids.contains(&"keyword_only"));
+        assert!(ids.contains(&"semantic_only"));
+    }
+
+    #[test]
+    fn semantic_search_skips_vectors_of_mismatched_dimension() {
+        let (_dir, index) = test_index();
+        index.insert(&test_record("a", "a.txt", "x")).unwrap();
+        index.upsert_vector("a", &[1.0, 0.0, 0.0]).unwrap();
+
+        // Querying with a different dimension must not panic or silently
+        // truncate — see `dot`'s length guard in `semantic_search`.
+        let results = index.semantic_search(&[1.0, 0.0], 10).unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn get_by_hash_finds_shared_hash_and_count_by_hash_tracks_remaining_references() {
+        let (_dir, index) = test_index();
+        let mut original = test_record("original", "a.txt", "hello world");
+        original.content_hash = "shared-hash".to_string();
+        index.insert(&original).unwrap();
+
+        let mut duplicate = test_record("duplicate", "b.txt", "hello world");
+        duplicate.content_hash = "shared-hash".to_string();
+        index.insert(&duplicate).unwrap();
+
+        assert_eq!(index.count_by_hash("shared-hash").unwrap(), 2);
+
+        let found = index.get_by_hash("shared-hash").unwrap().unwrap();
+        assert_eq!(found.content_hash, "shared-hash");
+
+        index.delete("duplicate").unwrap();
+        assert_eq!(
+            index.count_by_hash("shared-hash").unwrap(),
+            1,
+            "deleting one of two records sharing a hash must not drop the count to zero"
+        );
+
+        index.delete("original").unwrap();
+        assert_eq!(
+            index.count_by_hash("shared-hash").unwrap(),
+            0,
+            "deleting the last record referencing a hash must drop the count to zero"
+        );
+    }
+}
@@ -8,10 +8,14 @@ pub mod storage;
 pub mod index;
 pub mod extract;
 pub mod describe;
+pub mod embed;
+pub mod preview;
+pub mod webfetch;
 
-pub use schema::{FileRecord, FileMetadata, FileSearchResult, FileListResponse};
+pub use schema::{FileRecord, FileMetadata, FileSearchResult, FileListResponse, FileStatus};
 pub use index::FileIndex;
 pub use storage::FileStorage;
+pub use embed::Embedder;
 
 use anyhow::Result;
 use chrono::Local;
@@ -23,16 +27,32 @@ use uuid::Uuid;
 pub struct FtmsService {
     pub storage: FileStorage,
     pub index: Arc<FileIndex>,
+    /// Optional embedding backend. When set, background processing also
+    /// indexes a semantic vector for the file so `FileIndex::semantic_search`
+    /// and `hybrid_search` can retrieve it. When unset, FTMS falls back to
+    /// FTS5 only, same as before semantic search existed.
+    pub embedder: Option<Arc<dyn Embedder>>,
 }
 
 impl FtmsService {
     pub fn new(storage_dir: &str, workspace_dir: &Path) -> Result<Self> {
         let storage = FileStorage::new(storage_dir)?;
         let index = Arc::new(FileIndex::new(workspace_dir)?);
-        Ok(Self { storage, index })
+        Ok(Self { storage, index, embedder: None })
     }
 
-    /// Upload a file: store on disk, extract text, index metadata.
+    /// Attach an embedding backend so uploads are also indexed for semantic
+    /// search.
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Upload a file: store the blob and insert a `pending` record
+    /// immediately, then run extraction/description/thumbnailing/embedding
+    /// on a background task so a slow or failing processing step (a bad PDF,
+    /// an unreachable embedding backend) can't block or fail the upload
+    /// itself. Call `FtmsService::reprocess` to retry a `failed` file.
     pub async fn upload(
         &self,
         filename: &str,
@@ -42,32 +62,263 @@ impl FtmsService {
         let id = Uuid::new_v4().to_string();
         let mime_type = extract::guess_mime_type(filename);
 
-        // Store file on disk
-        let (rel_path, _abs_path) = self.storage.store(filename, data).await?;
+        // Store file on disk — content-addressed, so re-uploading identical
+        // bytes reuses the existing blob instead of writing a duplicate.
+        let (rel_path, _abs_path, content_hash) = self.storage.store(filename, data).await?;
 
-        // Extract text content
-        let extracted_text = extract::extract_text(data, &mime_type, filename)?;
-
-        // Generate AI description for media files
-        let ai_description = describe::describe_media(data, &mime_type, filename)?;
+        // If another record already finished processing this exact content,
+        // reuse its extracted text/description/thumbnail/dimensions instead
+        // of re-running the whole pipeline (extraction, AI description,
+        // thumbnailing, embedding) on bytes we've already indexed.
+        let reusable = self
+            .index
+            .get_by_hash(&content_hash)?
+            .filter(|r| r.status == FileStatus::Indexed);
 
         let record = FileRecord {
-            id,
+            id: id.clone(),
             filename: filename.to_string(),
-            mime_type,
+            mime_type: mime_type.clone(),
             file_path: rel_path,
             file_size: data.len() as u64,
-            extracted_text,
-            ai_description,
+            content_hash,
+            extracted_text: reusable.as_ref().and_then(|r| r.extracted_text.clone()),
+            ai_description: reusable.as_ref().and_then(|r| r.ai_description.clone()),
+            thumbnail_path: reusable.as_ref().and_then(|r| r.thumbnail_path.clone()),
+            width: reusable.as_ref().and_then(|r| r.width),
+            height: reusable.as_ref().and_then(|r| r.height),
+            duration_secs: reusable.as_ref().and_then(|r| r.duration_secs),
+            status: if reusable.is_some() { FileStatus::Indexed } else { FileStatus::Pending },
+            error: None,
             session_id: metadata.session_id,
             channel: metadata.channel,
             uploaded_at: Local::now().to_rfc3339(),
             tags: metadata.tags,
+            source_url: None,
         };
 
-        // Index in SQLite
         self.index.insert(&record)?;
+        if let Some(source) = &reusable {
+            // The new id has no row of its own in `file_vectors` yet — copy
+            // the source's embedding so it isn't silently invisible to
+            // `semantic_search`/`hybrid_search` despite being `Indexed`.
+            if let Some(vector) = self.index.get_vector(&source.id)? {
+                self.index.upsert_vector(&id, &vector)?;
+            }
+            // `source.tags` may mix the uploader's own tags with ones
+            // derived from media metadata during processing; there's no way
+            // to tell them apart after the fact, so copy the whole field
+            // rather than lose the derived half.
+            if let Some(source_tags) = &source.tags {
+                self.index.append_tags(&id, source_tags)?;
+            }
+        } else {
+            self.spawn_processing(id, data.to_vec(), mime_type, filename.to_string());
+        }
 
         Ok(record)
     }
+
+    /// Ingest a web page: fetch it, extract its readable main-content text
+    /// (see `webfetch::clean_html`), and index the cleaned text and title
+    /// the same way an uploaded file would be. Unlike `upload`, extraction
+    /// happens before the record is inserted — fetching *is* the slow,
+    /// fallible step here — and the stored blob holds the cleaned text,
+    /// not the original HTML.
+    pub async fn upload_url(&self, url: &str, metadata: FileMetadata) -> Result<FileRecord> {
+        let id = Uuid::new_v4().to_string();
+        let page = webfetch::fetch_url(url).await?;
+        let filename = page.title.clone().unwrap_or_else(|| url.to_string());
+
+        let (rel_path, _abs_path, content_hash) = self
+            .storage
+            .store(&format!("{}.html", id), page.text.as_bytes())
+            .await?;
+
+        let record = FileRecord {
+            id: id.clone(),
+            filename,
+            mime_type: "text/html".to_string(),
+            file_path: rel_path,
+            file_size: page.text.len() as u64,
+            content_hash,
+            extracted_text: Some(page.text.clone()),
+            ai_description: page.description.clone(),
+            thumbnail_path: None,
+            width: None,
+            height: None,
+            duration_secs: None,
+            status: FileStatus::Pending,
+            error: None,
+            session_id: metadata.session_id,
+            channel: metadata.channel,
+            uploaded_at: Local::now().to_rfc3339(),
+            tags: metadata.tags,
+            source_url: Some(url.to_string()),
+        };
+
+        self.index.insert(&record)?;
+        self.spawn_embed(id, page.text, page.description);
+
+        Ok(record)
+    }
+
+    /// Re-run the processing pipeline for an existing file — e.g. a `failed`
+    /// upload after fixing whatever made extraction/embedding fail, or a
+    /// `pending` one stuck because a worker crashed mid-run.
+    pub async fn reprocess(&self, id: &str) -> Result<()> {
+        let Some(record) = self.index.get(id)? else {
+            return Ok(());
+        };
+        let data = self.storage.read(&record.file_path).await?;
+        self.index.update_status(id, FileStatus::Pending, None)?;
+        self.spawn_processing(id.to_string(), data, record.mime_type, record.filename);
+        Ok(())
+    }
+
+    fn spawn_processing(&self, id: String, data: Vec<u8>, mime_type: String, filename: String) {
+        let index = Arc::clone(&self.index);
+        let storage = self.storage.clone();
+        let embedder = self.embedder.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::process(&storage, &index, &embedder, &id, &data, &mime_type, &filename).await {
+                let _ = index.update_status(&id, FileStatus::Failed, Some(&e.to_string()));
+            }
+        });
+    }
+
+    /// Embed already-extracted text in the background — the lightweight
+    /// counterpart to `spawn_processing` for sources like `upload_url` that
+    /// skip extraction/thumbnailing because their text is already known.
+    fn spawn_embed(&self, id: String, text: String, description: Option<String>) {
+        let index = Arc::clone(&self.index);
+        let embedder = self.embedder.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::embed_only(&index, &embedder, &id, &text, description.as_deref()).await {
+                let _ = index.update_status(&id, FileStatus::Failed, Some(&e.to_string()));
+            }
+        });
+    }
+
+    async fn embed_only(
+        index: &FileIndex,
+        embedder: &Option<Arc<dyn Embedder>>,
+        id: &str,
+        text: &str,
+        description: Option<&str>,
+    ) -> Result<()> {
+        index.update_status(id, FileStatus::Processing, None)?;
+
+        if let Some(embedder) = embedder {
+            let embeddable = [Some(text), description].into_iter().flatten().collect::<Vec<_>>().join("\n");
+            if !embeddable.trim().is_empty() {
+                let vector = embedder.embed(&embeddable)?;
+                index.upsert_vector(id, &vector)?;
+            }
+        }
+
+        index.update_status(id, FileStatus::Indexed, None)?;
+        Ok(())
+    }
+
+    /// The actual extraction/description/thumbnailing/embedding work, run
+    /// off the upload path by `spawn_processing`.
+    async fn process(
+        storage: &FileStorage,
+        index: &FileIndex,
+        embedder: &Option<Arc<dyn Embedder>>,
+        id: &str,
+        data: &[u8],
+        mime_type: &str,
+        filename: &str,
+    ) -> Result<()> {
+        index.update_status(id, FileStatus::Processing, None)?;
+
+        // Extract text content; audio/video carry no plain text, but often
+        // embed tags (title, artist, album, ...) worth indexing instead.
+        let mut extracted_text = extract::extract_text(data, mime_type, filename)?;
+        let media_metadata = extract::extract_media_metadata(data, mime_type)?;
+        if let Some(media_metadata) = &media_metadata {
+            extracted_text = media_metadata.to_text();
+            if let Some(derived_tags) = media_metadata.to_tags() {
+                index.append_tags(id, &derived_tags)?;
+            }
+        }
+
+        // Generate a gallery-sized preview for image/video uploads, and
+        // store it as its own content-addressed blob. Thumbnailing is
+        // best-effort: formats the `image` crate can't decode (SVG,
+        // HEIC/AVIF, a corrupt upload) must not abort the rest of the
+        // pipeline — fall back to no thumbnail so text/description
+        // extraction and indexing still happen.
+        let thumbnail = preview::generate_preview(data, mime_type).unwrap_or(None);
+        let thumbnail_path = match &thumbnail {
+            Some(bytes) => Some(storage.store("thumb.jpg", bytes).await?.0),
+            None => None,
+        };
+
+        // Generate AI description for media files — reuses the thumbnail
+        // (if any) instead of embedding the full original.
+        let ai_description = describe::describe_media(data, mime_type, filename, thumbnail.as_deref())?;
+
+        // Structured dimensions/duration so clients can reserve correct
+        // aspect-ratio placeholders without fetching the file.
+        let (width, height, duration_secs) = if mime_type.starts_with("image/") {
+            // Best-effort, same as the thumbnail above — an undecodable
+            // image shouldn't fail the whole upload over missing dimensions.
+            let dims = preview::probe_image_dimensions(data).unwrap_or(None);
+            (dims.map(|(w, _)| w), dims.map(|(_, h)| h), None)
+        } else if let Some(media_metadata) = &media_metadata {
+            (media_metadata.width, media_metadata.height, media_metadata.duration_secs)
+        } else {
+            (None, None, None)
+        };
+
+        index.update_content(id, extracted_text.as_deref(), ai_description.as_deref())?;
+        index.update_thumbnail(id, thumbnail_path.as_deref())?;
+        index.update_dimensions(id, width, height, duration_secs)?;
+
+        // Embed for semantic search, if an embedding backend is configured.
+        // `ai_description` for images/video carries a `[IMAGE:data:...]`
+        // base64 marker alongside the human-readable line — strip it so the
+        // embedder sees meaningful text instead of an oversized, meaningless
+        // data-URI blob.
+        if let Some(embedder) = embedder {
+            let embeddable = [extracted_text.as_deref(), ai_description.as_deref()]
+                .into_iter()
+                .flatten()
+                .map(describe::strip_image_marker)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !embeddable.trim().is_empty() {
+                let vector = embedder.embed(&embeddable)?;
+                index.upsert_vector(id, &vector)?;
+            }
+        }
+
+        index.update_status(id, FileStatus::Indexed, None)?;
+        Ok(())
+    }
+
+    /// Delete a file record and, if it was the last reference to its blob
+    /// (and its thumbnail's blob), the underlying blobs as well.
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let Some(record) = self.index.get(id)? else {
+            return Ok(());
+        };
+
+        self.index.delete(id)?;
+
+        if self.index.count_by_hash(&record.content_hash)? == 0 {
+            self.storage.delete(&record.file_path).await?;
+        }
+
+        if let Some(thumbnail_path) = &record.thumbnail_path {
+            if self.index.count_by_thumbnail_path(thumbnail_path)? == 0 {
+                self.storage.delete(thumbnail_path).await?;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,122 @@
+use anyhow::Result;
+
+/// Maximum edge length (px) for generated thumbnails.
+const MAX_DIMENSION: u32 = 512;
+
+/// Generate a downscaled preview thumbnail for `image/*` and `video/*`
+/// uploads, returned as JPEG bytes. Returns `None` for MIME types with no
+/// meaningful visual preview, or if thumbnailing isn't compiled in.
+pub fn generate_preview(data: &[u8], mime_type: &str) -> Result<Option<Vec<u8>>> {
+    if mime_type.starts_with("image/") {
+        generate_image_thumbnail(data)
+    } else if mime_type.starts_with("video/") {
+        generate_video_thumbnail(data)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Read just enough of an image to report its pixel dimensions, without
+/// decoding the full image.
+pub fn probe_image_dimensions(data: &[u8]) -> Result<Option<(u32, u32)>> {
+    #[cfg(feature = "rag-thumbnails")]
+    {
+        use std::io::Cursor;
+
+        let reader = image::ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+        Ok(reader.into_dimensions().ok())
+    }
+    #[cfg(not(feature = "rag-thumbnails"))]
+    {
+        let _ = data;
+        Ok(None)
+    }
+}
+
+fn generate_image_thumbnail(data: &[u8]) -> Result<Option<Vec<u8>>> {
+    #[cfg(feature = "rag-thumbnails")]
+    {
+        use image::imageops::FilterType;
+        use std::io::Cursor;
+
+        let img = image::load_from_memory(data)?;
+        // `resize` scales up to fill the bounds too; leave images that
+        // already fit as they are.
+        let thumb = if img.width() > MAX_DIMENSION || img.height() > MAX_DIMENSION {
+            img.resize(MAX_DIMENSION, MAX_DIMENSION, FilterType::Lanczos3)
+        } else {
+            img
+        };
+
+        let mut out = Vec::new();
+        thumb.write_to(&mut Cursor::new(&mut out), image::ImageFormat::Jpeg)?;
+        Ok(Some(out))
+    }
+    #[cfg(not(feature = "rag-thumbnails"))]
+    {
+        let _ = data;
+        Ok(None)
+    }
+}
+
+/// Extract a representative frame (seek to ~10% into the clip) and downscale
+/// it, via the system `ffmpeg`/`ffprobe` binaries.
+fn generate_video_thumbnail(data: &[u8]) -> Result<Option<Vec<u8>>> {
+    #[cfg(feature = "rag-thumbnails")]
+    {
+        use std::io::Write;
+        use std::process::Command;
+
+        let mut src = tempfile::Builder::new().suffix(".bin").tempfile()?;
+        src.write_all(data)?;
+
+        let duration = probe_duration_secs(src.path())?;
+        let seek = duration.map(|d| d * 0.1).unwrap_or(1.0);
+
+        let out = tempfile::Builder::new().suffix(".jpg").tempfile()?;
+        let status = Command::new("ffmpeg")
+            .args(["-y", "-ss", &format!("{:.2}", seek), "-i"])
+            .arg(src.path())
+            .args([
+                "-frames:v",
+                "1",
+                "-vf",
+                &format!(
+                    "scale='min({d},iw)':'min({d},ih)':force_original_aspect_ratio=decrease",
+                    d = MAX_DIMENSION
+                ),
+            ])
+            .arg(out.path())
+            .status()?;
+
+        if !status.success() {
+            return Ok(None);
+        }
+        Ok(Some(std::fs::read(out.path())?))
+    }
+    #[cfg(not(feature = "rag-thumbnails"))]
+    {
+        let _ = data;
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "rag-thumbnails")]
+fn probe_duration_secs(path: &std::path::Path) -> Result<Option<f64>> {
+    let output = std::process::Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(path)
+        .output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(text.trim().parse::<f64>().ok())
+}
@@ -1,5 +1,45 @@
 use serde::{Deserialize, Serialize};
 
+/// Where a file is in the background processing pipeline (text extraction,
+/// AI description, thumbnailing, embedding).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// Blob stored and record inserted; processing not started yet.
+    Pending,
+    /// A background worker is currently extracting/describing/embedding it.
+    Processing,
+    /// Processing completed successfully.
+    Indexed,
+    /// Processing failed — see `FileRecord.error`. Safe to retry.
+    Failed,
+}
+
+impl FileStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileStatus::Pending => "pending",
+            FileStatus::Processing => "processing",
+            FileStatus::Indexed => "indexed",
+            FileStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::str::FromStr for FileStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(FileStatus::Pending),
+            "processing" => Ok(FileStatus::Processing),
+            "indexed" => Ok(FileStatus::Indexed),
+            "failed" => Ok(FileStatus::Failed),
+            other => Err(anyhow::anyhow!("unknown file status: {}", other)),
+        }
+    }
+}
+
 /// A stored file record with metadata and extracted content.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileRecord {
@@ -8,12 +48,29 @@ pub struct FileRecord {
     pub mime_type: String,
     pub file_path: String,
     pub file_size: u64,
+    pub content_hash: String,
     pub extracted_text: Option<String>,
     pub ai_description: Option<String>,
+    /// Relative path of a downscaled preview blob (see `preview` module),
+    /// for gallery views that shouldn't have to fetch the original.
+    pub thumbnail_path: Option<String>,
+    /// Pixel dimensions, for images and video. Lets clients reserve a
+    /// correctly-proportioned placeholder before fetching the file.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Playback length in seconds, for audio and video.
+    pub duration_secs: Option<f64>,
+    /// Processing pipeline state — see `FileStatus`.
+    pub status: FileStatus,
+    /// Set when `status` is `Failed`; the error that aborted processing.
+    pub error: Option<String>,
     pub session_id: Option<String>,
     pub channel: Option<String>,
     pub uploaded_at: String,
     pub tags: Option<String>,
+    /// Source URL, for files ingested via `FtmsService::upload_url` rather
+    /// than a direct upload.
+    pub source_url: Option<String>,
 }
 
 /// Metadata sent with an upload request (not the file bytes themselves).
@@ -39,3 +96,26 @@ pub struct FileListResponse {
     pub offset: usize,
     pub limit: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn file_status_round_trips_through_as_str_and_from_str() {
+        for status in [
+            FileStatus::Pending,
+            FileStatus::Processing,
+            FileStatus::Indexed,
+            FileStatus::Failed,
+        ] {
+            assert_eq!(FileStatus::from_str(status.as_str()).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn file_status_from_str_rejects_unknown_values() {
+        assert!(FileStatus::from_str("archived").is_err());
+    }
+}
@@ -1,10 +1,15 @@
 use anyhow::{Context, Result};
-use chrono::Local;
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use tokio::fs;
-use uuid::Uuid;
 
-/// Manages file storage on disk, organized by date.
+/// Manages content-addressed file storage on disk.
+///
+/// Blobs are written once under `<hash[0..2]>/<hash[2..4]>/<hash>.<ext>`, so
+/// identical uploads with the same extension share a single file on disk
+/// regardless of how many `FileRecord`s reference them. The same bytes
+/// stored under a different extension are written as a separate blob.
+#[derive(Clone)]
 pub struct FileStorage {
     base_dir: PathBuf,
 }
@@ -16,33 +21,49 @@ impl FileStorage {
         Ok(Self { base_dir: base })
     }
 
-    /// Store file bytes, returns (relative_path, absolute_path).
+    /// Store file bytes, returning `(relative_path, absolute_path,
+    /// content_hash)`. `content_hash` is the hex SHA-256 digest used as the
+    /// blob's on-disk name — callers use it to dedup against
+    /// `FileIndex::get_by_hash` without re-hashing the data themselves.
+    ///
+    /// The on-disk filename is the hex SHA-256 digest of `data`, sharded by
+    /// its first four hex characters to keep any one directory small. If a
+    /// blob with that hash already exists, the write is skipped entirely.
     pub async fn store(
         &self,
         original_filename: &str,
         data: &[u8],
-    ) -> Result<(String, PathBuf)> {
-        let now = Local::now();
-        let date_dir = now.format("%Y/%m/%d").to_string();
-        let abs_dir = self.base_dir.join(&date_dir);
-        fs::create_dir_all(&abs_dir)
-            .await
-            .context("Failed to create date directory")?;
+    ) -> Result<(String, PathBuf, String)> {
+        let hash = Self::content_hash(data);
 
         let ext = Path::new(original_filename)
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("bin");
-        let file_id = Uuid::new_v4().to_string();
-        let stored_name = format!("{}.{}", file_id, ext);
+        let stored_name = format!("{}.{}", hash, ext);
 
+        let shard_dir = format!("{}/{}", &hash[0..2], &hash[2..4]);
+        let abs_dir = self.base_dir.join(&shard_dir);
+
+        let rel_path = format!("{}/{}", shard_dir, stored_name);
         let abs_path = abs_dir.join(&stored_name);
-        fs::write(&abs_path, data)
-            .await
-            .context("Failed to write file")?;
 
-        let rel_path = format!("{}/{}", date_dir, stored_name);
-        Ok((rel_path, abs_path))
+        if !abs_path.exists() {
+            fs::create_dir_all(&abs_dir)
+                .await
+                .context("Failed to create shard directory")?;
+            fs::write(&abs_path, data)
+                .await
+                .context("Failed to write file")?;
+        }
+
+        Ok((rel_path, abs_path, hash))
+    }
+
+    /// Compute the hex SHA-256 digest of `data`.
+    pub fn content_hash(data: &[u8]) -> String {
+        let digest = Sha256::digest(data);
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
     }
 
     /// Read file bytes by relative path.
@@ -51,7 +72,9 @@ impl FileStorage {
         fs::read(&abs).await.context("Failed to read file")
     }
 
-    /// Delete a file by relative path.
+    /// Unlink the blob at `rel_path`. Callers must ensure no remaining
+    /// `FileRecord` references this path's content hash before calling —
+    /// see `FtmsService::delete` for the reference-counted entry point.
     pub async fn delete(&self, rel_path: &str) -> Result<()> {
         let abs = self.base_dir.join(rel_path);
         if abs.exists() {
@@ -0,0 +1,184 @@
+use anyhow::Result;
+
+/// Result of fetching and cleaning a web page.
+pub struct WebPage {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub text: String,
+}
+
+/// Fetch `url` and extract its readable main-content text — same
+/// readability-style cleaning as `clean_html`, so a URL upload and an
+/// `.html` file upload of the same page end up with the same indexed text.
+pub async fn fetch_url(url: &str) -> Result<WebPage> {
+    #[cfg(feature = "rag-web")]
+    {
+        use anyhow::Context;
+
+        let body = reqwest::get(url)
+            .await
+            .context("Failed to fetch URL")?
+            .error_for_status()
+            .context("URL returned an error status")?
+            .text()
+            .await
+            .context("Failed to read response body")?;
+        Ok(extract_article(&body))
+    }
+    #[cfg(not(feature = "rag-web"))]
+    {
+        let _ = url;
+        anyhow::bail!("URL ingestion requires the rag-web feature")
+    }
+}
+
+/// Strip scripts/styles/nav chrome from `html` and return the highest-
+/// scoring subtree's text, readability-style. Returns `None` if the
+/// `rag-web` feature isn't compiled in or no readable text is found, so
+/// callers can fall back to a raw decode.
+pub fn clean_html(html: &str) -> Option<String> {
+    #[cfg(feature = "rag-web")]
+    {
+        let page = extract_article(html);
+        Some(page.text).filter(|t| !t.trim().is_empty())
+    }
+    #[cfg(not(feature = "rag-web"))]
+    {
+        let _ = html;
+        None
+    }
+}
+
+/// Tags whose text (including nested elements) never counts as article
+/// content — boilerplate chrome, not prose.
+#[cfg(feature = "rag-web")]
+const IGNORED_TAGS: &[&str] = &["script", "style", "noscript", "nav", "header", "footer"];
+
+/// Parse `html`, score every block-level element by text-to-link-density
+/// and paragraph count, and return the text of the highest-scoring one —
+/// a simplified version of the Readability.js heuristic.
+#[cfg(feature = "rag-web")]
+fn extract_article(html: &str) -> WebPage {
+    use scraper::{Html, Selector};
+
+    let document = Html::parse_document(html);
+
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let description = Selector::parse(r#"meta[name="description"]"#)
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .and_then(|el| el.value().attr("content"))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let block_selector = Selector::parse("div, article, section, main, body").unwrap();
+    let paragraph_selector = Selector::parse("p").unwrap();
+    let link_selector = Selector::parse("a").unwrap();
+
+    let mut best_score = f64::MIN;
+    let mut best_text = String::new();
+
+    for block in document.select(&block_selector) {
+        let text = normalize_whitespace(&visible_text(&block));
+        if text.len() < 50 {
+            continue;
+        }
+
+        let link_text_len: usize = block
+            .select(&link_selector)
+            .map(|a| visible_text(&a).len())
+            .sum();
+        let link_density = link_text_len as f64 / text.len() as f64;
+        let paragraph_count = block.select(&paragraph_selector).count();
+
+        // Reward paragraph-dense content, penalize link-heavy nav/chrome —
+        // a page's main article beats its sidebar and footer links.
+        let score = text.len() as f64 * (1.0 - link_density).max(0.05)
+            + paragraph_count as f64 * 50.0;
+
+        if score > best_score {
+            best_score = score;
+            best_text = text;
+        }
+    }
+
+    WebPage { title, description, text: best_text }
+}
+
+/// Collect text from `el`'s subtree, skipping script/style/nav chrome
+/// entirely rather than indexing their raw contents.
+#[cfg(feature = "rag-web")]
+fn visible_text(el: &scraper::ElementRef) -> String {
+    use scraper::node::Node;
+
+    let mut text = String::new();
+    for child in el.children() {
+        match child.value() {
+            Node::Text(t) => {
+                text.push_str(t);
+                text.push(' ');
+            }
+            Node::Element(e) if IGNORED_TAGS.contains(&e.name()) => {}
+            Node::Element(_) => {
+                if let Some(child_el) = scraper::ElementRef::wrap(child) {
+                    text.push_str(&visible_text(&child_el));
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(feature = "rag-web")]
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(all(test, feature = "rag-web"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_article_picks_the_main_content_over_nav_and_footer_chrome() {
+        let html = r#"
+            <html>
+              <head>
+                <title>  Example Article  </title>
+                <meta name="description" content="  A short summary.  ">
+              </head>
+              <body>
+                <nav><a href="/a">Home</a><a href="/b">About</a><a href="/c">Contact</a></nav>
+                <article>
+                  <p>This is the first paragraph of the real article, with enough
+                  prose to clearly out-score the navigation and footer links.</p>
+                  <p>A second paragraph keeps the paragraph count and text
+                  density high relative to the surrounding chrome.</p>
+                </article>
+                <footer><a href="/x">Terms</a><a href="/y">Privacy</a></footer>
+                <script>trackPageView();</script>
+              </body>
+            </html>
+        "#;
+
+        let page = extract_article(html);
+
+        assert_eq!(page.title.as_deref(), Some("Example Article"));
+        assert_eq!(page.description.as_deref(), Some("A short summary."));
+        assert!(page.text.contains("first paragraph of the real article"));
+        assert!(page.text.contains("second paragraph"));
+        assert!(!page.text.contains("Home"));
+        assert!(!page.text.contains("Terms"));
+        assert!(!page.text.contains("trackPageView"));
+    }
+
+    #[test]
+    fn clean_html_returns_none_for_content_free_markup() {
+        assert!(clean_html("<html><body><nav><a href=\"/\">Home</a></nav></body></html>").is_none());
+    }
+}